@@ -20,6 +20,10 @@ pub struct Args {
     /// Enable verbose logging for debugging purposes.
     #[arg(short, long, default_value_t = false)]
     pub verbose: bool,
+
+    /// Force the home-directory database even if a project-local `.fsk` exists.
+    #[arg(short, long, default_value_t = false)]
+    pub global: bool,
 }
 
 #[derive(Subcommand)]
@@ -33,9 +37,18 @@ pub enum Commands {
 
     /// Search for a keyword in titles and note contents.
     #[command(alias = "find")]
-    Search { 
-        /// The keyword or phrase to search for.
-        keyword: String 
+    Search {
+        /// The keyword or phrase to search for. Prefix with `tag:` to match
+        /// frontmatter tags instead (e.g. `tag:rust`).
+        keyword: String,
+
+        /// Print only per-note match counts instead of the matching lines.
+        #[arg(long)]
+        count: bool,
+
+        /// Match case exactly instead of folding case (the default).
+        #[arg(long)]
+        case_sensitive: bool,
     },
 
     /// Display the content of a specific note to stdout.
@@ -74,5 +87,12 @@ pub enum Commands {
     List {
         /// Optional: The folder/path to list (e.g., 'a7x')
         path: Option<String>,
+
+        /// Only list notes tagged with this frontmatter tag.
+        #[arg(long)]
+        tag: Option<String>,
     },
+
+    /// Create a `.fsk` marker in the current directory for a project-local database.
+    Init,
 }
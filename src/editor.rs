@@ -13,7 +13,12 @@ use crate::storage;
 /// Launches the preferred editor to create or modify a note.
 pub fn editor(title: &str) {
     // 1. Pelit RAM: Jangan alokasi String "nano" kalau env EDITOR udah ada.
-    let editor_cmd = env::var("EDITOR").unwrap_or_else(|_| "nano".to_string());
+    let editor_cmd = env::var("EDITOR").unwrap_or_else(|_| {
+        storage::config()
+            .default_editor
+            .clone()
+            .unwrap_or_else(|| "nano".to_string())
+    });
 
     let full_path = storage::resolve_path(title);
 
@@ -27,9 +32,18 @@ pub fn editor(title: &str) {
         }
     }
 
+    // 3. Seed brand-new notes with a YAML frontmatter block so tags are
+    // ready to fill in before the editor even opens.
+    if !full_path.exists() {
+        if let Err(e) = fs::write(&full_path, frontmatter_template(title)) {
+            error!("Critical: Failed to seed note '{}': {e}", title);
+            return;
+        }
+    }
+
     let filepath_str = full_path.to_string_lossy();
 
-    // 3. Execute and handle result
+    // 4. Execute and handle result
     if spawn_editor(&editor_cmd, &filepath_str) {
         info!("Note '{}' saved successfully.", title);
     } else {
@@ -37,6 +51,12 @@ pub fn editor(title: &str) {
     }
 }
 
+/// Builds the initial content for a brand-new note: a YAML frontmatter
+/// block with the title pre-filled and an empty `tags` list to edit.
+fn frontmatter_template(title: &str) -> String {
+    format!("---\ntitle: {title}\ntags: []\ncreated:\n---\n\n")
+}
+
 /// Spawns the editor process. Returns true if the process exited successfully.
 fn spawn_editor(cmd: &str, filepath: &str) -> bool {
     debug!("Executing: {} {}", cmd, filepath);
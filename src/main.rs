@@ -7,16 +7,44 @@
 mod cli;
 mod storage;
 mod editor;
+mod picker;
 
 use clap::Parser;
 use cli::{Args, Commands};
 use colored::*;
+use storage::BadEntry;
+
+/// Prints any paths that could not be traversed during a `list`/`search`,
+/// instead of letting them disappear silently.
+fn print_skipped(skipped: &[BadEntry]) {
+    if skipped.is_empty() {
+        return;
+    }
+
+    println!("{} {} path(s) skipped:", "⚠".yellow(), skipped.len());
+    for bad in skipped {
+        println!("  {} {}", "•".bright_black(), bad.to_string().bright_black());
+    }
+}
+
+/// Resolves `title` to an existing note, falling back to an interactive
+/// fuzzy picker over the whole database when it doesn't match directly.
+/// Returns `None` if nothing was found or the picker was cancelled.
+fn resolve_or_pick(title: &str) -> Option<String> {
+    if storage::resolve_path(title).is_file() {
+        return Some(title.to_string());
+    }
+
+    let (candidates, _) = storage::list(None, None);
+    picker::select_note(&candidates)
+}
 
 fn main() {
     // Initialize logger for debugging (controlled via RUST_LOG env var)
     env_logger::init();
 
     let args = Args::parse();
+    storage::set_force_global(args.global);
 
     // Linear command dispatching
     // Standard professional flow: Match -> Execute -> Handle Error
@@ -28,7 +56,12 @@ fn main() {
 
         // Retrieves and prints the raw content of a specific note.
         Commands::Get { title } => {
-            match storage::read(title) {
+            let Some(resolved) = resolve_or_pick(title) else {
+                eprintln!("{} Note '{}' not found.", "✘".red(), title.yellow());
+                return;
+            };
+
+            match storage::read(&resolved) {
                 Ok(content) => {
                     if content.trim().is_empty() {
                         println!("{}", "Note is empty.".bright_black());
@@ -38,9 +71,9 @@ fn main() {
                 }
                 Err(e) => {
                     eprintln!(
-                        "{} Failed to read '{}': {}", 
-                        "✘".red(), 
-                        title.yellow(), 
+                        "{} Failed to read '{}': {}",
+                        "✘".red(),
+                        resolved.yellow(),
                         e.to_string().bright_black()
                     );
                 }
@@ -48,31 +81,34 @@ fn main() {
         }
 
         // Lists all available notes in the database recursively.
-        Commands::List { path } => {
-            let files = storage::list(path.as_ref());
-            
+        Commands::List { path, tag } => {
+            let (files, skipped) = storage::list(path.as_ref(), tag.as_deref());
+
             if files.is_empty() {
                 println!("{} {}", "󰉖".red(), "No notes found in database.".bright_black());
+                print_skipped(&skipped);
                 return;
             }
 
             println!("{} {}:", "󰠮".cyan(), "Your Knowledge Base".bold());
             println!("{}", "─".repeat(40).bright_black());
-            
+
             for file in &files {
                 println!("  {} {}", "•".blue(), file.bright_white());
             }
-            
+
             println!("{}", "─".repeat(40).bright_black());
             println!("{} {} total notes", "󰇄".yellow(), files.len());
+            print_skipped(&skipped);
         }
 
         // Searches through titles and file contents for a specific keyword.
-        Commands::Search { keyword } => {
-            let results = storage::search(keyword);
-    
+        Commands::Search { keyword, count, case_sensitive } => {
+            let (results, skipped) = storage::search(keyword, !case_sensitive);
+
             if results.is_empty() {
                 println!("{} '{}'", "󰍉 No results found for".red(), keyword.yellow());
+                print_skipped(&skipped);
                 return;
             }
 
@@ -80,35 +116,69 @@ fn main() {
             println!("{}", "─".repeat(40).bright_black());
 
             for res in &results {
+                if *count {
+                    let label = if res.is_title_match {
+                        "title match".to_string()
+                    } else {
+                        format!("{} match(es)", res.matches.len())
+                    };
+                    println!("  {} {} — {}", "•".blue(), res.title.bright_white(), label.bright_black());
+                    continue;
+                }
+
                 if res.is_title_match {
                     println!("{} {}", "󰈚".blue(), res.title.bold().bright_white());
                 } else {
                     println!("{} {}", "󰉈".green(), res.title.bright_white());
-                    if let Some(text) = &res.preview {
-                        println!("   {} {}", "↳".bright_black(), text.italic().bright_black());
+                    for m in &res.matches {
+                        if let Some(before) = &m.context_before {
+                            println!("      {}", before.bright_black());
+                        }
+                        println!(
+                            "   {} {}: {}",
+                            "↳".bright_black(),
+                            m.line_number.to_string().bright_black(),
+                            m.text.italic().bright_black()
+                        );
+                        if let Some(after) = &m.context_after {
+                            println!("      {}", after.bright_black());
+                        }
                     }
                 }
             }
-            
+
             println!("{}", "─".repeat(40).bright_black());
             println!("{} {} result(s) found", "󰇄".yellow(), results.len());
+            print_skipped(&skipped);
         }
 
         // Removes a specific note from the database.
         Commands::Remove { title } => {
-            match storage::remove(title) {
+            // Folders are handed to `storage::remove` as-is; only note titles
+            // go through the picker fallback.
+            let resolved = if storage::resolve_path("").join(title).is_dir() {
+                Some(title.clone())
+            } else {
+                resolve_or_pick(title)
+            };
+            let Some(resolved) = resolved else {
+                eprintln!("{} Note '{}' not found.", "✘".red(), title.yellow());
+                return;
+            };
+
+            match storage::remove(&resolved) {
                 Ok(_) => {
                     println!(
-                        "{} Note '{}' deleted successfully.", 
-                        "🗑".red(), 
-                        title.yellow()
+                        "{} Note '{}' deleted successfully.",
+                        "🗑".red(),
+                        resolved.yellow()
                     );
                 }
                 Err(e) => {
                     eprintln!(
-                        "{} Failed to delete '{}': {}", 
-                        "✘".red(), 
-                        title.yellow(), 
+                        "{} Failed to delete '{}': {}",
+                        "✘".red(),
+                        resolved.yellow(),
                         e.to_string().bright_black()
                     );
                 }
@@ -117,8 +187,8 @@ fn main() {
 
         // Export a database from local
         Commands::Export { folder, destination } => {
-            let target = if folder == "all" || folder == "." { "" } else { &folder };
-            match storage::export_folder(target, &destination) {
+            let target = if folder == "all" || folder == "." { "" } else { folder };
+            match storage::export_folder(target, destination) {
                 Ok(_) => println!("{} Exported '{}' to '{}' successfully.", "📦".green(), folder, destination),
                 Err(e) => eprintln!("{} Export failed: {}", "✘".red(), e),
             }
@@ -126,7 +196,7 @@ fn main() {
 
         // Import note 
         Commands::Import { source } => {
-            match storage::export_folder("", &source) { // reuse logic copy_dir
+            match storage::export_folder("", source) { // reuse logic copy_dir
                 Ok(_) => println!("{} Imported notes from '{}' successfully.", "📥".green(), source),
                 Err(e) => eprintln!("{} Import failed: {}", "✘".red(), e),
             }
@@ -134,25 +204,46 @@ fn main() {
 
         // Renames or moves a note, including cross-directory moves.
         Commands::Move { from, to } => {
-            match storage::rename(from, to) {
+            let Some(resolved_from) = resolve_or_pick(from) else {
+                eprintln!("{} Note '{}' not found.", "✘".red(), from.yellow());
+                return;
+            };
+
+            match storage::rename(&resolved_from, to) {
                 Ok(_) => {
                     println!(
-                        "{} Moved: {} {} {}", 
-                        "󰁔".green(), 
-                        from.bright_black(), 
-                        "➔".bright_black(), 
+                        "{} Moved: {} {} {}",
+                        "󰁔".green(),
+                        resolved_from.bright_black(),
+                        "➔".bright_black(),
                         to.yellow()
                     );
                 }
                 Err(e) => {
                     eprintln!(
-                        "{} Failed to move '{}': {}", 
-                        "✘".red(), 
-                        from.yellow(), 
+                        "{} Failed to move '{}': {}",
+                        "✘".red(),
+                        resolved_from.yellow(),
                         e.to_string().bright_black()
                     );
                 }
             }
         }
+
+        // Creates a `.fsk` marker directory to root a project-local database.
+        Commands::Init => {
+            match storage::init_local() {
+                Ok(_) => println!(
+                    "{} Initialized a project-local knowledge base in '{}'.",
+                    "✔".green(),
+                    ".fsk".yellow()
+                ),
+                Err(e) => eprintln!(
+                    "{} Failed to initialize local database: {}",
+                    "✘".red(),
+                    e.to_string().bright_black()
+                ),
+            }
+        }
     }
 }
@@ -0,0 +1,33 @@
+// Copyright (c) 2024 Danydev
+// Licensed under the MIT License.
+//
+// picker.rs: Interactive fuzzy picker used when a command's title argument
+// doesn't resolve to an existing note. Gated behind the `tui` feature so
+// the default build stays dependency-light.
+
+#[cfg(feature = "tui")]
+use dialoguer::{theme::ColorfulTheme, FuzzySelect};
+
+/// Lets the user fuzzy-filter and arrow-key select one of `candidates`.
+/// Returns `None` if the user cancels, if there's nothing to pick from, if
+/// stdout isn't an attended terminal (scripts, CI, cron, piped output), or
+/// if the `tui` feature is disabled.
+#[cfg(feature = "tui")]
+pub fn select_note(candidates: &[String]) -> Option<String> {
+    if candidates.is_empty() || !console::user_attended() {
+        return None;
+    }
+
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select a note")
+        .items(candidates)
+        .interact_opt()
+        .ok()??;
+
+    candidates.get(selection).cloned()
+}
+
+#[cfg(not(feature = "tui"))]
+pub fn select_note(_candidates: &[String]) -> Option<String> {
+    None
+}
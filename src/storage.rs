@@ -1,20 +1,163 @@
 // Copyright (c) 2024 Danydev
 // Licensed under the MIT License.
 //
-// storage.rs: Data access layer. Handles file I/O and recursive directory traversal
-// with a focus on memory efficiency and linear execution.
+// storage.rs: Data access layer. Handles file I/O and directory traversal
+// (with per-file matching run in parallel) with a focus on memory
+// efficiency and resilience to unreadable entries.
 
+use std::collections::VecDeque;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-/// Resolves the absolute path to a note. 
+use log::warn;
+use rayon::prelude::*;
+
+/// Optional overrides read from `$XDG_CONFIG_HOME/fsk/config.toml`.
+/// Both fields are optional; an absent or unparsable config simply leaves
+/// the XDG defaults in place.
+#[derive(Default)]
+pub struct Config {
+    pub db_root: Option<PathBuf>,
+    pub default_editor: Option<String>,
+}
+
+/// Loaded once per process and reused by every `resolve_path` call.
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Returns the process-wide config, loading it from disk on first access.
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(load_config)
+}
+
+fn load_config() -> Config {
+    let path = xdg_config_home().join("fsk").join("config.toml");
+    let Ok(raw) = fs::read_to_string(&path) else {
+        return Config::default();
+    };
+
+    let mut config = Config::default();
+    for (line_no, raw_line) in raw.lines().enumerate() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            warn!("{}:{}: ignoring unparsable config line: {raw_line:?}", path.display(), line_no + 1);
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim().trim_matches('"').trim_matches('\'');
+
+        match key {
+            "db_root" => config.db_root = Some(PathBuf::from(shellexpand_home(value))),
+            "default_editor" => config.default_editor = Some(value.to_string()),
+            _ => warn!("{}:{}: ignoring unknown config key '{key}'", path.display(), line_no + 1),
+        }
+    }
+    config
+}
+
+/// Strips a trailing `# comment`, ignoring `#` characters inside quotes so
+/// a path like `"/a/#b"` survives intact.
+fn strip_comment(line: &str) -> &str {
+    let mut in_single = false;
+    let mut in_double = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' if !in_single => in_double = !in_double,
+            '\'' if !in_double => in_single = !in_single,
+            '#' if !in_single && !in_double => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Expands a leading `~/` to $HOME, the only expansion the config file needs.
+fn shellexpand_home(value: &str) -> String {
+    match value.strip_prefix("~/") {
+        Some(rest) => env::var("HOME")
+            .map(|h| format!("{h}/{rest}"))
+            .unwrap_or_else(|_| value.to_string()),
+        None => value.to_string(),
+    }
+}
+
+fn xdg_data_home() -> PathBuf {
+    env::var("XDG_DATA_HOME").map(PathBuf::from).unwrap_or_else(|_| {
+        env::var("HOME")
+            .map(|h| PathBuf::from(h).join(".local").join("share"))
+            .unwrap_or_else(|_| PathBuf::from("./db"))
+    })
+}
+
+fn xdg_config_home() -> PathBuf {
+    env::var("XDG_CONFIG_HOME").map(PathBuf::from).unwrap_or_else(|_| {
+        env::var("HOME")
+            .map(|h| PathBuf::from(h).join(".config"))
+            .unwrap_or_else(|_| PathBuf::from("./.config"))
+    })
+}
+
+/// Set once in `main` from the `--global` flag. When `true`, `resolve_path`
+/// always uses the home-directory database even if a project-local `.fsk`
+/// marker is found.
+static FORCE_GLOBAL: OnceLock<bool> = OnceLock::new();
+
+/// Forces `resolve_path` to ignore any project-local `.fsk` marker.
+/// Must be called, if at all, before the first `resolve_path` call.
+pub fn set_force_global(force: bool) {
+    let _ = FORCE_GLOBAL.set(force);
+}
+
+fn force_global() -> bool {
+    *FORCE_GLOBAL.get_or_init(|| false)
+}
+
+/// The home-directory database root: config.toml's `db_root`, or
+/// `$XDG_DATA_HOME/fsk/db`.
+fn global_db_root() -> PathBuf {
+    config()
+        .db_root
+        .clone()
+        .unwrap_or_else(|| xdg_data_home().join("fsk").join("db"))
+}
+
+/// Walks up from the current directory looking for a `.fsk` marker, the
+/// same way tools like git climb parents looking for `.git`. Returns the
+/// database root inside the marker if one is found.
+fn find_local_db() -> Option<PathBuf> {
+    let mut dir = env::current_dir().ok()?;
+    loop {
+        if dir.join(".fsk").is_dir() {
+            return Some(dir.join(".fsk").join("db"));
+        }
+        if !dir.pop() {
+            return None;
+        }
+    }
+}
+
+/// Creates a `.fsk` marker directory in the current working directory,
+/// rooting a project-local note database alongside the code.
+pub fn init_local() -> std::io::Result<()> {
+    let marker = env::current_dir()?.join(".fsk");
+    fs::create_dir_all(marker.join("db"))
+}
+
+/// Resolves the absolute path to a note.
 /// If title is empty, returns the base database directory.
 pub fn resolve_path(title: &str) -> PathBuf {
-    // Priority: $HOME/.fsk/db or fallback to current directory
-    let base = env::var("HOME")
-        .map(|h| PathBuf::from(h).join(".fsk").join("db"))
-        .unwrap_or_else(|_| PathBuf::from("./db"));
+    // Priority: project-local ".fsk" marker (unless --global), then the
+    // home-directory database.
+    let base = if force_global() {
+        global_db_root()
+    } else {
+        find_local_db().unwrap_or_else(global_db_root)
+    };
 
     if title.is_empty() {
         base
@@ -29,23 +172,122 @@ pub fn read(title: &str) -> Result<String, std::io::Error> {
     fs::read_to_string(path)
 }
 
-/// Recursively lists notes. Supports filtering by a sub-path.
-pub fn list(sub_path: Option<&String>) -> Vec<String> {
+/// A directory entry that could not be traversed or classified.
+/// Kept separate from the main results so callers can surface what was
+/// skipped instead of silently losing it.
+pub enum BadEntry {
+    /// The OS returned an error while statting or reading this path
+    /// (permission denied, I/O error, ...).
+    OsError { path: PathBuf, reason: String },
+    /// The path exists but is neither a regular file nor a directory.
+    /// Symlinks are not followed, so every symlink is reported here.
+    UnreadableType { path: PathBuf, reason: String },
+}
+
+impl std::fmt::Display for BadEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BadEntry::OsError { path, reason } | BadEntry::UnreadableType { path, reason } => {
+                write!(f, "{} ({})", path.display(), reason)
+            }
+        }
+    }
+}
+
+/// Lists notes. Supports filtering by a sub-path and, optionally, by a tag
+/// parsed from each note's YAML frontmatter.
+/// Returns the sorted note titles alongside any paths that could not be
+/// traversed.
+pub fn list(sub_path: Option<&String>, tag: Option<&str>) -> (Vec<String>, Vec<BadEntry>) {
     let db_path = resolve_path("");
-    
+
     let start_path = match sub_path {
         Some(p) => db_path.join(p),
         None => db_path.clone(),
     };
 
-    let mut files = Vec::new();
-    
-    if start_path.exists() && start_path.is_dir() {
-        visit_dirs(&start_path, &db_path, &mut files);
+    if !(start_path.exists() && start_path.is_dir()) {
+        return (Vec::new(), Vec::new());
     }
-    
+
+    let (md_files, bad) = walk_collect(&start_path);
+
+    // Tag filtering has to open and parse every candidate file, so it's
+    // dispatched across threads with rayon, same as `search_by_tag`.
+    let mut files: Vec<String> = md_files
+        .par_iter()
+        .filter(|path| match tag {
+            Some(tag) => has_tag(path, tag),
+            None => true,
+        })
+        .filter_map(|path| path.strip_prefix(&db_path).ok())
+        .map(|rel| rel.with_extension("").to_string_lossy().to_string())
+        .collect();
+
     files.sort();
-    files
+    (files, bad)
+}
+
+/// Returns true if the note at `path` has `tag` in its frontmatter.
+fn has_tag(path: &Path, tag: &str) -> bool {
+    fs::read_to_string(path)
+        .map(|content| parse_tags(&content).iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        .unwrap_or(false)
+}
+
+/// Extracts the `tags` list from a note's YAML frontmatter without parsing
+/// the whole block into a struct. Returns an empty vec if there's no
+/// frontmatter or no `tags` line. Understands both flow style
+/// (`tags: [a, b]`) and block style (`tags:` followed by indented `- a`
+/// list items).
+pub fn parse_tags(content: &str) -> Vec<String> {
+    let Some(frontmatter) = extract_frontmatter(content) else { return Vec::new() };
+    let lines: Vec<&str> = frontmatter.lines().collect();
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some(raw) = line.trim().strip_prefix("tags:") else { continue };
+        let raw = raw.trim();
+
+        if !raw.is_empty() {
+            return parse_tag_list(raw);
+        }
+
+        // Block style: the value is on indented `- item` lines that follow.
+        return lines[i + 1..]
+            .iter()
+            .take_while(|l| l.starts_with(char::is_whitespace) && l.trim_start().starts_with('-'))
+            .map(|l| l.trim_start().trim_start_matches('-').trim())
+            .map(|t| t.trim_matches('"').trim_matches('\'').to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+    }
+    Vec::new()
+}
+
+/// Returns the text between the opening and closing `---` fences, if any.
+fn extract_frontmatter(content: &str) -> Option<&str> {
+    let rest = content.strip_prefix("---\n")?;
+    let end = rest.find("\n---")?;
+    Some(&rest[..end])
+}
+
+/// Parses a YAML flow sequence like `[a, "b", 'c']` into a list of tags.
+fn parse_tag_list(raw: &str) -> Vec<String> {
+    raw.trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|t| t.trim().trim_matches('"').trim_matches('\'').to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// A single matching line within a note, with one line of context on
+/// either side (like `grep -C1`).
+pub struct Match {
+    pub line_number: usize,
+    pub text: String,
+    pub context_before: Option<String>,
+    pub context_after: Option<String>,
 }
 
 /// Full-text search optimized for memory.
@@ -53,72 +295,155 @@ pub fn list(sub_path: Option<&String>) -> Vec<String> {
 pub struct SearchResult {
     pub title: String,
     pub is_title_match: bool,
-    pub preview: Option<String>,
+    pub matches: Vec<Match>,
 }
 
-pub fn search(keyword: &str) -> Vec<SearchResult> {
+/// Searches titles and note contents for `keyword`.
+/// A `tag:<name>` query matches notes whose frontmatter tags contain
+/// `<name>` instead of matching against raw content. `ignore_case`
+/// controls whether plain keyword matching folds case.
+/// Returns the matches alongside any paths that could not be traversed.
+pub fn search(keyword: &str, ignore_case: bool) -> (Vec<SearchResult>, Vec<BadEntry>) {
     let db_path = resolve_path("");
-    let kw_lower = keyword.to_lowercase(); // Allocated once
-    let mut results = Vec::new();
-    
-    // Standard recursive walker logic but simplified
-    walk_and_search(&db_path, &db_path, &kw_lower, &mut results);
-    results
-}
+    let (md_files, bad) = walk_collect(&db_path);
 
-// --- Private Helpers (The "Linear & Clean" way) ---
+    let results = match keyword.strip_prefix("tag:") {
+        Some(tag) => search_by_tag(&md_files, &db_path, tag),
+        None => search_by_keyword(&md_files, &db_path, keyword, ignore_case),
+    };
 
-fn visit_dirs(dir: &Path, base: &Path, acc: &mut Vec<String>) {
-    let Ok(entries) = fs::read_dir(dir) else { return };
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            visit_dirs(&path, base, acc);
-        } else if path.extension().map_or(false, |e| e == "md") {
-            if let Ok(rel) = path.strip_prefix(base) {
-                acc.push(rel.with_extension("").to_string_lossy().to_string());
-            }
-        }
-    }
+    (results, bad)
 }
 
-fn walk_and_search(dir: &Path, base: &Path, kw: &str, results: &mut Vec<SearchResult>) {
-    let Ok(entries) = fs::read_dir(dir) else { return };
-    
-    for entry in entries.flatten() {
-        let path = entry.path();
-        
-        if path.is_dir() {
-            walk_and_search(&path, base, kw, results);
-            continue;
-        }
+/// Matches titles and, lazily, file content against a plain keyword,
+/// reporting every matching line (with surrounding context) rather than
+/// just the first.
+fn search_by_keyword(
+    files: &[PathBuf],
+    db_path: &Path,
+    keyword: &str,
+    ignore_case: bool,
+) -> Vec<SearchResult> {
+    let kw = if ignore_case { keyword.to_lowercase() } else { keyword.to_string() };
+    let fold = |s: &str| if ignore_case { s.to_lowercase() } else { s.to_string() };
 
-        if path.extension().map_or(false, |e| e == "md") {
-            let rel_path = path.strip_prefix(base).unwrap_or(&path);
+    // Title matches are essentially free; content matches require reading
+    // the file, so the batch is dispatched across threads with rayon.
+    files
+        .par_iter()
+        .filter_map(|path| {
+            let rel_path = path.strip_prefix(db_path).unwrap_or(path);
             let title = rel_path.with_extension("").to_string_lossy().to_string();
-            
+
             // 1. Title Match (Fast Path)
-            if title.to_lowercase().contains(kw) {
-                results.push(SearchResult {
+            if fold(&title).contains(&kw) {
+                return Some(SearchResult {
                     title,
                     is_title_match: true,
-                    preview: None,
+                    matches: Vec::new(),
                 });
-                continue; // Skip reading file content to save RAM/IO
             }
 
             // 2. Content Match (Lazy Loading)
-            if let Ok(content) = fs::read_to_string(&path) {
-                if let Some(line) = content.lines().find(|l| l.to_lowercase().contains(kw)) {
-                    results.push(SearchResult {
-                        title,
-                        is_title_match: false,
-                        preview: Some(line.trim().to_string()),
-                    });
+            let content = fs::read_to_string(path).ok()?;
+            let lines: Vec<&str> = content.lines().collect();
+
+            let matches: Vec<Match> = lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| fold(line).contains(&kw))
+                .map(|(i, line)| Match {
+                    line_number: i + 1,
+                    text: line.trim().to_string(),
+                    context_before: i.checked_sub(1).map(|j| lines[j].trim().to_string()),
+                    context_after: lines.get(i + 1).map(|l| l.trim().to_string()),
+                })
+                .collect();
+
+            if matches.is_empty() {
+                return None;
+            }
+            Some(SearchResult {
+                title,
+                is_title_match: false,
+                matches,
+            })
+        })
+        .collect()
+}
+
+/// Matches notes whose frontmatter `tags` contain `tag`.
+fn search_by_tag(files: &[PathBuf], db_path: &Path, tag: &str) -> Vec<SearchResult> {
+    files
+        .par_iter()
+        .filter_map(|path| {
+            if !has_tag(path, tag) {
+                return None;
+            }
+            let rel_path = path.strip_prefix(db_path).unwrap_or(path);
+            let title = rel_path.with_extension("").to_string_lossy().to_string();
+            Some(SearchResult {
+                title,
+                is_title_match: true,
+                matches: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+// --- Private Helpers ---
+
+/// Breadth-first traversal of `start`, collecting every `.md` file found.
+/// Unlike a recursive `entries.flatten()` walk, entries that fail to stat
+/// or read are reported back as `BadEntry` rather than silently dropped.
+fn walk_collect(start: &Path) -> (Vec<PathBuf>, Vec<BadEntry>) {
+    let mut files = Vec::new();
+    let mut bad = Vec::new();
+    let mut queue: VecDeque<PathBuf> = VecDeque::from([start.to_path_buf()]);
+
+    while let Some(dir) = queue.pop_front() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                bad.push(BadEntry::OsError { path: dir, reason: e.to_string() });
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    bad.push(BadEntry::OsError { path: dir.clone(), reason: e.to_string() });
+                    continue;
                 }
+            };
+
+            let path = entry.path();
+            let file_type = match entry.file_type() {
+                Ok(ft) => ft,
+                Err(e) => {
+                    bad.push(BadEntry::OsError { path, reason: e.to_string() });
+                    continue;
+                }
+            };
+
+            if file_type.is_dir() {
+                queue.push_back(path);
+            } else if file_type.is_file() {
+                if path.extension().is_some_and(|e| e == "md") {
+                    files.push(path);
+                }
+            } else {
+                bad.push(BadEntry::UnreadableType {
+                    path,
+                    reason: "not a regular file or directory".to_string(),
+                });
             }
         }
     }
+
+    (files, bad)
 }
 
 /// Deletes a note or an entire directory from the database.
@@ -216,3 +541,57 @@ fn copy_dir_recursive(src: &Path, dest: &Path) -> std::io::Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tags_flow_style() {
+        let content = "---\ntitle: x\ntags: [rust, yaml]\ncreated:\n---\nbody";
+        assert_eq!(parse_tags(content), vec!["rust", "yaml"]);
+    }
+
+    #[test]
+    fn parse_tags_block_style() {
+        let content = "---\ntitle: x\ntags:\n  - rust\n  - yaml\ncreated:\n---\nbody";
+        assert_eq!(parse_tags(content), vec!["rust", "yaml"]);
+    }
+
+    #[test]
+    fn parse_tags_empty_flow_list() {
+        let content = "---\ntitle: x\ntags: []\ncreated:\n---\nbody";
+        assert!(parse_tags(content).is_empty());
+    }
+
+    #[test]
+    fn parse_tags_missing_frontmatter() {
+        assert!(parse_tags("no frontmatter here").is_empty());
+    }
+
+    #[test]
+    fn parse_tags_unterminated_fence() {
+        let content = "---\ntitle: x\ntags: [rust]\n";
+        assert!(parse_tags(content).is_empty());
+    }
+
+    #[test]
+    fn has_tag_reads_frontmatter_from_disk() {
+        let dir = env::temp_dir().join(format!("fsk-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("note.md");
+        fs::write(&path, "---\ntags: [demo]\n---\n").unwrap();
+
+        assert!(has_tag(&path, "demo"));
+        assert!(!has_tag(&path, "other"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn strip_comment_respects_quotes() {
+        assert_eq!(strip_comment(r#"db_root = "/a/b" # comment"#), r#"db_root = "/a/b" "#);
+        assert_eq!(strip_comment("plain = value # trailing note"), "plain = value ");
+        assert_eq!(strip_comment("no_comment = here"), "no_comment = here");
+    }
+}